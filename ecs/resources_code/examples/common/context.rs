@@ -0,0 +1,47 @@
+// Bevy systems are hard to unit test in isolation: exercising one means standing up a whole
+// App::build().run() and driving its schedule. These traits let game logic be written against
+// "some place resources live" instead of Bevy directly, so it can be driven by a plain struct
+// in a test instead
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+pub trait GetResource<T: 'static> {
+	fn get(&self) -> &T;
+}
+
+pub trait GetResourceMut<T: 'static>: GetResource<T> {
+	fn get_mut(&mut self) -> &mut T;
+}
+
+// A MockContext can stand in for any combination of GetResource/GetResourceMut bounds, so
+// individual examples declare their own Context trait listing the resources they touch
+// and get a MockContext for free
+#[derive(Default)]
+pub struct MockContext {
+	resources: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl MockContext {
+	pub fn insert<T: 'static>(&mut self, resource: T) {
+		self.resources.insert(TypeId::of::<T>(), Box::new(resource));
+	}
+}
+
+impl<T: 'static> GetResource<T> for MockContext {
+	fn get(&self) -> &T {
+		self.resources
+			.get(&TypeId::of::<T>())
+			.and_then(|boxed| boxed.downcast_ref::<T>())
+			.expect("resource not present in MockContext")
+	}
+}
+
+impl<T: 'static> GetResourceMut<T> for MockContext {
+	fn get_mut(&mut self) -> &mut T {
+		self.resources
+			.get_mut(&TypeId::of::<T>())
+			.and_then(|boxed| boxed.downcast_mut::<T>())
+			.expect("resource not present in MockContext")
+	}
+}
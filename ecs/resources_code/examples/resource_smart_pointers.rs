@@ -1,4 +1,11 @@
+// Lives under examples/common/ rather than directly in examples/ -- a bare .rs file dropped
+// into examples/ gets auto-discovered by Cargo as its own example target, and one with no
+// fn main fails to build with E0601
+#[path = "common/context.rs"]
+mod context;
+
 use bevy::prelude::*;
+use context::{GetResource, GetResourceMut, MockContext};
 use rand::Rng;
 use std::collections::HashMap;
 use std::fmt;
@@ -7,12 +14,13 @@ use std::fmt;
 struct Score(u32);
 
 // These derives let us use Player as a key in our HashMap later
-#[derive(Hash, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
 enum Player {
 	Player1,
 	Player2,
 }
 
+#[derive(Debug)]
 struct Winner(Player);
 
 // The Display trait lets us control how these types are printed
@@ -41,63 +49,163 @@ impl core::fmt::Display for Winner {
 	}
 }
 
+type ScoreMap = HashMap<Player, Score>;
+
+// update_score, determine_winner and show_winner only ever need the score map and the
+// winner, so we bundle exactly those bounds into one name instead of writing both out
+// at every call site
+trait Context:
+	GetResource<ScoreMap> + GetResourceMut<ScoreMap> + GetResource<Winner> + GetResourceMut<Winner>
+{
+}
+
+impl<T> Context for T where
+	T: GetResource<ScoreMap> + GetResourceMut<ScoreMap> + GetResource<Winner> + GetResourceMut<Winner>
+{
+}
+
+// The real, Bevy-backed Context. It borrows straight from the system params Bevy already
+// handed the calling system, so there's no copying of the score map on every frame
+struct BevyContext<'a> {
+	score_map: ResMut<'a, ScoreMap>,
+	winner: ResMut<'a, Winner>,
+}
+
+impl<'a> GetResource<ScoreMap> for BevyContext<'a> {
+	fn get(&self) -> &ScoreMap {
+		&self.score_map
+	}
+}
+
+impl<'a> GetResourceMut<ScoreMap> for BevyContext<'a> {
+	fn get_mut(&mut self) -> &mut ScoreMap {
+		&mut self.score_map
+	}
+}
+
+impl<'a> GetResource<Winner> for BevyContext<'a> {
+	fn get(&self) -> &Winner {
+		&self.winner
+	}
+}
+
+impl<'a> GetResourceMut<Winner> for BevyContext<'a> {
+	fn get_mut(&mut self) -> &mut Winner {
+		&mut self.winner
+	}
+}
+
 fn main() {
 	App::build()
 		.add_plugins(MinimalPlugins)
 		// Compound types like this are their own type, so can be fetched nicely by our scheduler
-		.init_resource::<HashMap<Player, Score>>()
+		.init_resource::<ScoreMap>()
 		.add_startup_system(initialize_scores.system())
 		// By the completely unfair rules of our game, Player1 wins ties
 		.add_resource(Winner(Player::Player1))
-		.add_system(update_score.system())
-		.add_system(determine_winner.system())
-		.add_system(show_winner.system())
+		.add_system(update_score_system.system())
+		.add_system(determine_winner_system.system())
+		.add_system(show_winner_system.system())
 		.run();
 }
 
 // Rather than trying to specify a starting value at compile time, we can initialize it with its Default value
 // Then we can set it within a system using more complex logic
-fn initialize_scores(mut score_map: ResMut<HashMap<Player, Score>>) {
+fn initialize_scores(mut score_map: ResMut<ScoreMap>) {
 	score_map.insert(Player::Player1, Score(0));
 	score_map.insert(Player::Player2, Score(0));
 }
 
-// We're modifying the score_map here, so we need to access them mutably with ResMut
-// Note that we need mut in front of the parameter name as well
-fn update_score(mut score_map: ResMut<HashMap<Player, Score>>) {
+// These three systems take &mut impl Context instead of Res/ResMut directly, so they can be
+// exercised with a MockContext in a test instead of requiring App::build().run()
+
+// We're modifying the score_map here, so we need to access it mutably
+fn update_score(ctx: &mut impl Context) {
 	let mut rng = rand::thread_rng();
 
-	for (_, score) in score_map.iter_mut() {
+	for (_, score) in GetResourceMut::<ScoreMap>::get_mut(ctx).iter_mut() {
 		// We need to access the 0th field of our simple tuple struct Score
 		*score = Score(score.0 + rng.gen_range(0..10));
 	}
 }
 
 // We're only reading our score_map, but need to write to our winner parameter
-fn determine_winner(score_map: Res<HashMap<Player, Score>>, mut winner: ResMut<Winner>) {
-	// Notice how Rust automatically derefences score_map here
-	// This works when we're trying to assign a resource or component to a value
-	// Or when we're using a method doesn't exist on our wrapper typ
-	let player_1_score = score_map.get(&Player::Player1).unwrap();
-	let player_2_score = score_map.get(&Player::Player2).unwrap();
+fn determine_winner(ctx: &mut impl Context) {
+	let player_1_score = GetResource::<ScoreMap>::get(ctx)
+		.get(&Player::Player1)
+		.unwrap()
+		.0;
+	let player_2_score = GetResource::<ScoreMap>::get(ctx)
+		.get(&Player::Player2)
+		.unwrap()
+		.0;
 
-	// You can impl std::comp::Ord on your types to overload your comparison operators
-	if player_1_score.0 >= player_2_score.0 {
-		// The automatic dereferencing doesn't work here, because we're trying to assign to, rather than access the value
-		// So Rust can't infer what we want to do
-		*winner = Winner(Player::Player1);
+	// You can impl std::cmp::Ord on your types to overload your comparison operators
+	*GetResourceMut::<Winner>::get_mut(ctx) = if player_1_score >= player_2_score {
+		Winner(Player::Player1)
 	} else {
-		*winner = Winner(Player::Player2);
-	}
+		Winner(Player::Player2)
+	};
 }
 
 // Finally, we just need to read the scores and winner to print them
-fn show_winner(score_map: Res<HashMap<Player, Score>>, winner: Res<Winner>) {
+fn show_winner(ctx: &mut impl Context) {
+	let score_map = GetResource::<ScoreMap>::get(ctx);
 	let player_1_score = score_map.get(&Player::Player1).unwrap();
 	let player_2_score = score_map.get(&Player::Player2).unwrap();
 
 	println!("Player 1's score: {}", player_1_score);
 	println!("Player 2's score: {}", player_2_score);
 	// We want to print the winner, not the reference to the winner
-	println!("Right now, {} is the winner!", *winner);
+	println!("Right now, {} is the winner!", GetResource::<Winner>::get(ctx));
+}
+
+// Thin adapters so Bevy's scheduler can call the context-agnostic logic above: each one just
+// gathers the real Res/ResMut params into a BevyContext and hands it off
+fn update_score_system(score_map: ResMut<ScoreMap>, winner: ResMut<Winner>) {
+	update_score(&mut BevyContext { score_map, winner });
+}
+
+fn determine_winner_system(score_map: ResMut<ScoreMap>, winner: ResMut<Winner>) {
+	determine_winner(&mut BevyContext { score_map, winner });
+}
+
+fn show_winner_system(score_map: ResMut<ScoreMap>, winner: ResMut<Winner>) {
+	show_winner(&mut BevyContext { score_map, winner });
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn mock_scores(player_1: u32, player_2: u32) -> MockContext {
+		let mut ctx = MockContext::default();
+		let mut score_map = ScoreMap::new();
+		score_map.insert(Player::Player1, Score(player_1));
+		score_map.insert(Player::Player2, Score(player_2));
+		ctx.insert(score_map);
+		ctx.insert(Winner(Player::Player1));
+		ctx
+	}
+
+	#[test]
+	fn player_1_wins_outright() {
+		let mut ctx = mock_scores(10, 4);
+		determine_winner(&mut ctx);
+		assert_eq!(GetResource::<Winner>::get(&ctx).0, Player::Player1);
+	}
+
+	#[test]
+	fn player_2_wins_when_strictly_ahead() {
+		let mut ctx = mock_scores(3, 8);
+		determine_winner(&mut ctx);
+		assert_eq!(GetResource::<Winner>::get(&ctx).0, Player::Player2);
+	}
+
+	#[test]
+	fn ties_go_to_player_1() {
+		let mut ctx = mock_scores(6, 6);
+		determine_winner(&mut ctx);
+		assert_eq!(GetResource::<Winner>::get(&ctx).0, Player::Player1);
+	}
 }
@@ -1,8 +1,8 @@
-// IMPORTANT NOTE:
-// This example does not currently work properly and skips events
-
+use bevy::app::stage;
+use bevy::diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin};
 use bevy::prelude::*;
 use rand::Rng;
+use std::collections::VecDeque;
 use std::thread::sleep;
 // We're using bevy's re-exported time types
 // because std::time isn't supported on wasm
@@ -14,35 +14,51 @@ struct ImportantMessage {
 }
 
 struct TimeBudget {
+	// What we'd like to spend per frame when nothing else is under load
+	base_duration: Duration,
+	// However much frame times grow, we never shrink past this
+	min_duration: Duration,
+	// What do_work actually uses this frame; adjusted by shrink_budget_under_load
 	duration: Duration,
 }
 
-// This example demonstrates pattern with Events
-// because there's any easy way to track and save progress
-// But you could do this with any system that you can safely pause and defer
+// Events<T>/EventReader<T> are built for draining a batch once per frame: EventReader::iter
+// advances its own bookkeeping for the *whole* batch the moment it's called, not per item
+// actually consumed. So breaking out of a `for event in reader.iter(&events)` loop partway
+// through still marks the unread tail as read -- exactly the "skips events" bug this example
+// is about. A plain persisted queue sidesteps that: pop_front() only removes what do_work
+// actually got to, so whatever's left at the front is still there next frame
+#[derive(Default)]
+struct MessageQueue(VecDeque<ImportantMessage>);
+
+// This example demonstrates a time-budgeted system that can safely pause partway
+// through its backlog and pick back up on a later frame without losing work
 fn main() {
 	App::build()
 		.add_plugins(MinimalPlugins)
-		.init_resource::<Events<ImportantMessage>>()
+		.add_plugin(FrameTimeDiagnosticsPlugin::default())
+		.init_resource::<MessageQueue>()
 		.add_system(send_events.system())
-		// We could use FrameTimeDiagnostics instead to calibrate this
-		// and slowly decrease our budget when our frame times start to increase
 		.add_resource(TimeBudget {
-			duration: Duration::new(0, 3 * 10 ^ 8),
+			base_duration: Duration::new(0, 300_000_000),
+			min_duration: Duration::new(0, 50_000_000),
+			duration: Duration::new(0, 300_000_000),
 		})
+		// Both systems touch TimeBudget, but add_system alone only stops them running
+		// concurrently -- it says nothing about which goes first within the stage. Putting
+		// the shrink in PRE_UPDATE, ahead of do_work's default UPDATE stage, is what
+		// actually guarantees do_work sees this frame's shrunk budget rather than last
+		// frame's
+		.add_system_to_stage(stage::PRE_UPDATE, shrink_budget_under_load.system())
 		.add_system(do_work.system())
 		.run();
 }
 
-fn send_events(
-	time: Res<Time>,
-	mut events: ResMut<Events<ImportantMessage>>,
-	mut message_number: Local<u32>,
-) {
+fn send_events(time: Res<Time>, mut queue: ResMut<MessageQueue>, mut message_number: Local<u32>) {
 	let n = rand::thread_rng().gen_range(0..5);
 	for _ in 0..n {
 		*message_number += 1;
-		events.send(ImportantMessage {
+		queue.0.push_back(ImportantMessage {
 			message_number: *message_number,
 			time_stamp: time.seconds_since_startup(),
 		});
@@ -51,19 +67,35 @@ fn send_events(
 	}
 }
 
-fn do_work(
-	time: Res<Time>,
-	events: Res<Events<ImportantMessage>>,
-	mut event_reader: Local<EventReader<ImportantMessage>>,
-	time_budget: Res<TimeBudget>,
-) {
+// When frame times climb above our 60fps target, do_work gets less time to chew
+// through the backlog, so a heavy frame doesn't get even heavier
+fn shrink_budget_under_load(diagnostics: Res<Diagnostics>, mut time_budget: ResMut<TimeBudget>) {
+	let frame_time = diagnostics
+		.get(FrameTimeDiagnosticsPlugin::FRAME_TIME)
+		.and_then(|diagnostic| diagnostic.average());
+
+	// Not enough samples yet to have an average
+	let frame_time = match frame_time {
+		Some(frame_time) => frame_time,
+		None => return,
+	};
+
+	let target_frame_time = 1.0 / 60.0;
+	let slowdown = (frame_time / target_frame_time).max(1.0);
+
+	let shrunk = time_budget.base_duration.as_secs_f64() / slowdown;
+	time_budget.duration =
+		Duration::from_secs_f64(shrunk.max(time_budget.min_duration.as_secs_f64()));
+}
+
+fn do_work(time: Res<Time>, mut queue: ResMut<MessageQueue>, time_budget: Res<TimeBudget>) {
 	// We can't use Res<Time> here, since it only updates at the start of each tick
 	let system_start = Instant::now();
 
-	for event in event_reader.iter(&events) {
+	while let Some(event) = queue.0.pop_front() {
 		// Sleeping for 0.1 seconds
 		// Processing these events sure does take a while!
-		sleep(Duration::new(0, 10 ^ 8));
+		sleep(Duration::new(0, 100_000_000));
 
 		println!(
 			"Message {:?} sent at {:?} was processed at {:?}",
@@ -73,10 +105,12 @@ fn do_work(
 		);
 
 		// We have to check whether to break the loop here, rather than the beginning
-		// to avoid dropping events
-		// The event counter is updated upon iteration
+		// to avoid dropping events: pop_front already removed `event` from the queue,
+		// so everything still in `queue` genuinely hasn't been processed yet
 		if system_start.elapsed() >= time_budget.duration {
 			break;
 		}
 	}
+	// Anything left in `queue` stays there for next frame -- nothing is skipped, since
+	// we only ever remove an event once we've actually processed it
 }
@@ -0,0 +1,157 @@
+// Events<T> (see concurrency.rs) dispatches everything sent since the last update, once per
+// frame -- fine for input and messages, but no good for modelling things that happen at a
+// specific future moment, like a cooldown expiring or a unit arriving at a waypoint
+// This plugin schedules typed events against an explicit simulation clock instead, and lets
+// handlers enqueue more events as a result of the ones they just ran
+
+use bevy::app::{AppBuilder, Plugin};
+use bevy::prelude::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::marker::PhantomData;
+
+struct ScheduledEvent<T> {
+	time: f64,
+	payload: T,
+	// Runs after the event is dispatched, so it can schedule follow-up events of its own
+	handler: Option<Box<dyn FnMut(T, f64, &mut SimQueue<T>) + Send + Sync>>,
+}
+
+// BinaryHeap is a max-heap and f64 isn't Ord, so we order scheduled events by time ourselves
+// and rely on SimQueue always popping through Reverse to get the earliest one out first
+impl<T> PartialEq for ScheduledEvent<T> {
+	fn eq(&self, other: &Self) -> bool {
+		self.time == other.time
+	}
+}
+
+impl<T> Eq for ScheduledEvent<T> {}
+
+impl<T> PartialOrd for ScheduledEvent<T> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<T> Ord for ScheduledEvent<T> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		self.time
+			.partial_cmp(&other.time)
+			.unwrap_or(Ordering::Equal)
+	}
+}
+
+// Generic over T the same way MaxUnits<T> and run_timer::<T> are, so scheduling a
+// CooldownExpired queue doesn't collide with a SpawnWave queue
+struct SimQueue<T> {
+	heap: BinaryHeap<std::cmp::Reverse<ScheduledEvent<T>>>,
+	// The driving system stops once the clock would pass this
+	horizon: f64,
+}
+
+impl<T> SimQueue<T> {
+	// Schedules `payload` to fire at `now + delay`; `handler` runs once it does
+	fn schedule(
+		&mut self,
+		now: f64,
+		delay: f64,
+		payload: T,
+		handler: impl FnMut(T, f64, &mut SimQueue<T>) + Send + Sync + 'static,
+	) {
+		self.heap.push(std::cmp::Reverse(ScheduledEvent {
+			time: now + delay,
+			payload,
+			handler: Some(Box::new(handler)),
+		}));
+	}
+}
+
+// Shared across every SimQueue<T>, since there's only one simulated present moment
+#[derive(Default)]
+struct SimClock {
+	now: f64,
+}
+
+struct DiscreteEventPlugin<T> {
+	horizon: f64,
+	phantom: PhantomData<T>,
+}
+
+impl<T> DiscreteEventPlugin<T> {
+	fn new(horizon: f64) -> Self {
+		DiscreteEventPlugin {
+			horizon,
+			phantom: PhantomData,
+		}
+	}
+}
+
+impl<T: Send + Sync + 'static> Plugin for DiscreteEventPlugin<T> {
+	fn build(&self, app: &mut AppBuilder) {
+		app.add_resource(SimQueue::<T> {
+			heap: BinaryHeap::new(),
+			horizon: self.horizon,
+		})
+		.init_resource::<SimClock>()
+		.add_system(drive_simulation::<T>.thread_local_system());
+	}
+}
+
+// Thread-local: a handler can enqueue follow-up events, which needs mutable access to the
+// very SimQueue<T> we're draining
+fn drive_simulation<T: Send + Sync + 'static>(_world: &mut World, resources: &mut Resources) {
+	loop {
+		let mut queue = resources.get_mut::<SimQueue<T>>().unwrap();
+
+		let next = match queue.heap.peek() {
+			Some(std::cmp::Reverse(scheduled)) if scheduled.time <= queue.horizon => {
+				queue.heap.pop().unwrap().0
+			}
+			_ => break,
+		};
+
+		drop(queue);
+
+		let mut clock = resources.get_mut::<SimClock>().unwrap();
+		clock.now = next.time;
+		drop(clock);
+
+		if let Some(mut handler) = next.handler {
+			let mut queue = resources.get_mut::<SimQueue<T>>().unwrap();
+			handler(next.payload, next.time, &mut queue);
+		}
+	}
+}
+
+struct CooldownExpired {
+	unit: &'static str,
+}
+
+fn main() {
+	App::build()
+		.add_plugins(MinimalPlugins)
+		.add_plugin(DiscreteEventPlugin::<CooldownExpired>::new(10.0))
+		.add_startup_system(schedule_cooldowns.thread_local_system())
+		.run();
+}
+
+fn schedule_cooldowns(_world: &mut World, resources: &mut Resources) {
+	let now = resources.get::<SimClock>().unwrap().now;
+	let mut queue = resources.get_mut::<SimQueue<CooldownExpired>>().unwrap();
+
+	// Archer's cooldown repeatedly reschedules itself; Catapult's fires once
+	queue.schedule(now, 2.0, CooldownExpired { unit: "Archer" }, archer_cooldown);
+	queue.schedule(
+		now,
+		5.0,
+		CooldownExpired { unit: "Catapult" },
+		|event, now, _queue| println!("{}'s cooldown expired at {:?}", event.unit, now),
+	);
+}
+
+// A named fn rather than a closure, so the handler can pass itself along when rescheduling;
+// the queue's horizon is what eventually stops it
+fn archer_cooldown(event: CooldownExpired, now: f64, queue: &mut SimQueue<CooldownExpired>) {
+	println!("{}'s cooldown expired at {:?}", event.unit, now);
+	queue.schedule(now, 2.0, event, archer_cooldown);
+}
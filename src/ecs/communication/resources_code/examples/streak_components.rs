@@ -0,0 +1,67 @@
+// PlayerColor (see adding_resources.rs) models state as a single global resource, shared by
+// the whole game. This example instead puts the enum on a component, so each entity owns and
+// mutates its own copy independently
+use bevy::prelude::*;
+use rand::Rng;
+use std::fmt;
+
+#[derive(Clone, Copy)]
+enum PlayerStreak {
+    Hot(usize),
+    Neutral,
+    Cold(usize),
+}
+
+impl Default for PlayerStreak {
+    fn default() -> Self {
+        PlayerStreak::Neutral
+    }
+}
+
+// The Display trait lets us control how these types are printed
+impl fmt::Display for PlayerStreak {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlayerStreak::Hot(rounds) => write!(f, "on a {}-round hot streak", rounds),
+            PlayerStreak::Neutral => write!(f, "not on a streak"),
+            PlayerStreak::Cold(rounds) => write!(f, "on a {}-round cold streak", rounds),
+        }
+    }
+}
+
+struct PlayerName(String);
+
+fn main() {
+    App::build()
+        .add_plugins(MinimalPlugins)
+        .add_startup_system(spawn_players.system())
+        .add_system(advance_streaks.system())
+        .run();
+}
+
+fn spawn_players(mut commands: Commands) {
+    commands
+        .spawn((PlayerName("Player 1".to_string()), PlayerStreak::default()))
+        .spawn((PlayerName("Player 2".to_string()), PlayerStreak::default()));
+}
+
+// Queries every entity with a streak and advances it based on a randomly rolled scoring
+// outcome for this round; a real game would read this from wherever rounds are resolved
+fn advance_streaks(mut query: Query<(&PlayerName, &mut PlayerStreak)>) {
+    let mut rng = rand::thread_rng();
+
+    for (name, mut streak) in query.iter_mut() {
+        let scored_this_round = rng.gen_bool(0.5);
+
+        *streak = match (scored_this_round, *streak) {
+            // Scoring while already hot extends the streak; scoring from cold or neutral starts a fresh one
+            (true, PlayerStreak::Hot(rounds)) => PlayerStreak::Hot(rounds + 1),
+            (true, _) => PlayerStreak::Hot(1),
+            // Missing while already cold extends it; missing from hot or neutral starts a fresh one
+            (false, PlayerStreak::Cold(rounds)) => PlayerStreak::Cold(rounds + 1),
+            (false, _) => PlayerStreak::Cold(1),
+        };
+
+        println!("{} is {}", name.0, *streak);
+    }
+}
@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+
+// MyTimer/run_timer::<T> (see system_local_resources.rs) tick down from Res<Time>,
+// so how many ticks a timer takes to fire depends on how long each frame actually ran
+// This version counts plain updates instead, so the same run always fires on the same tick
+// no matter how fast or slow the machine running it is
+
+struct TimerEntry {
+	remaining: u64,
+	// Some(n) reschedules for every n ticks; None fires once and is dropped
+	period: Option<u64>,
+	// FnMut rather than plain FnOnce, so an `every` closure can keep running on each
+	// reschedule instead of only firing the first time
+	closure: Option<Box<dyn FnMut(&mut World) + Send + Sync>>,
+}
+
+#[derive(Default)]
+struct Timers {
+	entries: Vec<TimerEntry>,
+}
+
+impl Timers {
+	// Runs `closure` once, `ticks` updates from now. A 0-tick timer would underflow on its
+	// very first decrement, so we treat it the same as 1: it fires on the next update
+	fn after(&mut self, ticks: u64, closure: impl FnMut(&mut World) + Send + Sync + 'static) {
+		self.entries.push(TimerEntry {
+			remaining: ticks.max(1),
+			period: None,
+			closure: Some(Box::new(closure)),
+		});
+	}
+
+	// Runs `closure` every `ticks` updates, starting `ticks` updates from now. As with
+	// `after`, a 0-tick period is treated as 1 to avoid underflowing `remaining`
+	fn every(&mut self, ticks: u64, closure: impl FnMut(&mut World) + Send + Sync + 'static) {
+		let ticks = ticks.max(1);
+		self.entries.push(TimerEntry {
+			remaining: ticks,
+			period: Some(ticks),
+			closure: Some(Box::new(closure)),
+		});
+	}
+}
+
+fn main() {
+	App::build()
+		.add_plugins(MinimalPlugins)
+		.init_resource::<Timers>()
+		.add_startup_system(schedule_timers.thread_local_system())
+		.add_system(tick_timers.thread_local_system())
+		.run();
+}
+
+fn schedule_timers(_world: &mut World, resources: &mut Resources) {
+	let mut timers = resources.get_mut::<Timers>().unwrap();
+
+	timers.after(5, |_world| println!("5 ticks have passed, just this once"));
+	timers.every(2, |_world| println!("2 ticks have passed, again"));
+}
+
+// A thread-local system, since it needs direct World access to run the scheduled closures
+fn tick_timers(world: &mut World, resources: &mut Resources) {
+	// Pass 1: decrement every entry and pull out the closures that are due this tick
+	// We can't call a closure here while we're still borrowing `Timers` to read it --
+	// the closure takes `&mut World`, and scheduling more timers from inside one would
+	// mean reaching back into this same resource, so we defer the calls until this
+	// borrow has ended
+	let mut due: Vec<(usize, Box<dyn FnMut(&mut World) + Send + Sync>)> = Vec::new();
+
+	{
+		let mut timers = resources.get_mut::<Timers>().unwrap();
+		for (i, entry) in timers.entries.iter_mut().enumerate() {
+			entry.remaining -= 1;
+			if entry.remaining == 0 {
+				match entry.period {
+					Some(period) => entry.remaining = period,
+					None => entry.remaining = 0,
+				}
+				due.push((i, entry.closure.take().unwrap()));
+			}
+		}
+	}
+
+	// Pass 2: run the due closures now that `Timers` isn't borrowed anymore
+	for (i, mut closure) in due {
+		closure(world);
+
+		let mut timers = resources.get_mut::<Timers>().unwrap();
+		if let Some(entry) = timers.entries.get_mut(i) {
+			// Recurring timers keep their closure around for next time; one-shot
+			// timers leave theirs as None, marking the entry for removal below
+			if entry.period.is_some() {
+				entry.closure = Some(closure);
+			}
+		}
+	}
+
+	resources
+		.get_mut::<Timers>()
+		.unwrap()
+		.entries
+		.retain(|entry| entry.closure.is_some());
+}